@@ -1,12 +1,15 @@
-use std::sync::Mutex;
 use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use iced::futures::Stream;
 use iced::widget::{column, container, text, toggler};
 use iced::{Element, Length, Size, Subscription, Task, Theme, window};
 
-use crate::smt::{self, SmtStatus};
+use crate::notification::{self, Level, Notification};
+use crate::privileged::CancelToken;
+use crate::setting::{Setting, SettingState};
+use crate::smt::{SmtError, SmtStatus};
 use crate::tray::TrayEvent;
 
 // Global channel receiver for tray events (set from main)
@@ -16,12 +19,42 @@ pub fn set_tray_receiver(receiver: mpsc::Receiver<TrayEvent>) {
     *TRAY_RECEIVER.lock().unwrap() = Some(receiver);
 }
 
+// Global channel sender for pushing status updates out to the tray icon
+// (set from main, mirroring TRAY_RECEIVER for the opposite direction)
+static TRAY_STATUS_SENDER: Mutex<Option<mpsc::Sender<SmtStatus>>> = Mutex::new(None);
+
+pub fn set_tray_status_sender(sender: mpsc::Sender<SmtStatus>) {
+    *TRAY_STATUS_SENDER.lock().unwrap() = Some(sender);
+}
+
+fn push_tray_status(status: SmtStatus) {
+    let sender = TRAY_STATUS_SENDER.lock().unwrap();
+    if let Some(ref sender) = *sender {
+        if sender.send(status).is_err() {
+            log::warn!("tray status channel closed; could not push update");
+        }
+    }
+}
+
+/// Lossy projection of a generalized setting state back onto `SmtStatus`,
+/// used only to keep the tray icon (which still only shows one state) in
+/// sync with whichever setting lives at index 0.
+fn tray_status_for(state: SettingState) -> SmtStatus {
+    match state {
+        SettingState::On => SmtStatus::On,
+        SettingState::Off => SmtStatus::Off,
+        SettingState::Locked => SmtStatus::ForceOff,
+        SettingState::NotSupported => SmtStatus::NotSupported,
+        SettingState::Unknown => SmtStatus::Unknown,
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum Message {
-    SmtToggled(bool),
-    SmtStatusUpdated(SmtStatus),
+    SettingToggled(usize, bool),
+    SettingStateUpdated(usize, SettingState),
     RefreshStatus,
-    SetSmtResult(Result<(), String>),
+    SetSettingResult(usize, u64, Result<(), SmtError>),
     WindowClosed(window::Id),
     TrayEvent(TrayEvent),
     GtkTick,
@@ -29,15 +62,36 @@ pub enum Message {
 }
 
 pub struct App {
-    smt_status: SmtStatus,
-    is_toggling: bool,
-    error_message: Option<String>,
+    settings: Vec<Arc<dyn Setting>>,
+    states: Vec<SettingState>,
+    /// The in-flight toggle request for each setting (indexed the same as
+    /// `settings`/`states`), if any: the value being applied, a generation
+    /// id (so a stale result from a superseded request can be told apart
+    /// from the current one), and its cancellation handle. Each setting
+    /// tracks its own slot so toggling one doesn't cancel or get confused
+    /// with another's in-flight apply.
+    pending_toggles: Vec<Option<(bool, u64, CancelToken)>>,
+    next_toggle_id: u64,
+    /// Transient status messages shown at the bottom of the window, pruned
+    /// as they expire (see `GtkTick`).
+    notifications: Vec<Notification>,
     window_id: Option<window::Id>,
 }
 
 impl App {
-    pub fn new() -> (Self, Task<Message>) {
-        let status = smt::read_smt_status().unwrap_or(SmtStatus::Unknown);
+    pub fn new(settings: Vec<Arc<dyn Setting>>) -> (Self, Task<Message>) {
+        let states: Vec<SettingState> = settings
+            .iter()
+            .map(|setting| {
+                setting.read_state().unwrap_or_else(|e| {
+                    log::warn!("failed to read initial state for {}: {e}", setting.label());
+                    SettingState::Unknown
+                })
+            })
+            .collect();
+        if let Some(&state) = states.first() {
+            push_tray_status(tray_status_for(state));
+        }
 
         // Open the initial window
         let (id, open_task) = window::open(window::Settings {
@@ -49,9 +103,11 @@ impl App {
 
         (
             Self {
-                smt_status: status,
-                is_toggling: false,
-                error_message: None,
+                pending_toggles: settings.iter().map(|_| None).collect(),
+                settings,
+                states,
+                next_toggle_id: 0,
+                notifications: Vec::new(),
                 window_id: Some(id),
             },
             open_task.map(Message::WindowOpened),
@@ -60,108 +116,197 @@ impl App {
 
     pub fn update(&mut self, message: Message) -> Task<Message> {
         match message {
-            Message::SmtToggled(enabled) => {
-                self.is_toggling = true;
-                self.error_message = None;
+            Message::SettingToggled(index, enabled) => {
+                let Some(setting) = self.settings.get(index).cloned() else {
+                    log::warn!("toggle requested for unknown setting index {index}");
+                    return Task::none();
+                };
+                log::info!(
+                    "user requested toggle of {}: enabled={enabled}",
+                    setting.label()
+                );
+
+                let current = self
+                    .states
+                    .get(index)
+                    .copied()
+                    .unwrap_or(SettingState::Unknown);
+                if !current.is_controllable() {
+                    log::warn!(
+                        "ignoring toggle request for {}; not controllable in state {current:?}",
+                        setting.label()
+                    );
+                    self.push_notification(
+                        format!("{} cannot be changed while {current:?}", setting.label()),
+                        Level::Error,
+                    );
+                    return Task::none();
+                }
+
+                // Only one apply per setting can be in flight; abort
+                // whatever was pending for this index (e.g. the user
+                // flipped the toggle back mid-apply). Other settings' own
+                // pending toggles are untouched.
+                if let Some(slot) = self.pending_toggles.get_mut(index) {
+                    if let Some((_, _, previous)) = slot.take() {
+                        log::info!("cancelling previous pending toggle for {}", setting.label());
+                        previous.cancel();
+                    }
+                }
+
+                self.next_toggle_id += 1;
+                let request_id = self.next_toggle_id;
+                let cancel = CancelToken::new();
+                if let Some(slot) = self.pending_toggles.get_mut(index) {
+                    *slot = Some((enabled, request_id, cancel.clone()));
+                }
 
                 Task::perform(
                     async move {
-                        tokio::task::spawn_blocking(move || smt::set_smt_enabled(enabled))
-                            .await
-                            .map_err(|e| e.to_string())?
-                            .map_err(|e| e.to_string())
+                        let result = match tokio::task::spawn_blocking(move || {
+                            setting.apply(enabled, &cancel)
+                        })
+                        .await
+                        {
+                            Ok(result) => result,
+                            Err(join_err) => Err(SmtError::Io(join_err.to_string())),
+                        };
+                        (index, request_id, result)
+                    },
+                    |(index, request_id, result)| {
+                        Message::SetSettingResult(index, request_id, result)
                     },
-                    Message::SetSmtResult,
                 )
             }
-            Message::SetSmtResult(result) => {
-                self.is_toggling = false;
+            Message::SetSettingResult(index, request_id, result) => {
+                let is_current = matches!(
+                    self.pending_toggles.get(index),
+                    Some(Some((_, id, _))) if *id == request_id
+                );
+                if !is_current {
+                    log::debug!(
+                        "ignoring stale toggle result for setting {index}, request {request_id}"
+                    );
+                    return Task::none();
+                }
+                let (enabled, _, _) = self.pending_toggles[index].take().unwrap();
                 match result {
                     Ok(()) => {
-                        // Refresh status after successful toggle
-                        return Task::perform(
-                            async {
-                                tokio::task::spawn_blocking(|| {
-                                    smt::read_smt_status().unwrap_or(SmtStatus::Unknown)
-                                })
-                                .await
-                                .unwrap_or(SmtStatus::Unknown)
-                            },
-                            Message::SmtStatusUpdated,
-                        );
+                        log::info!("toggle applied successfully for setting {index}");
+                        if let Some(setting) = self.settings.get(index).cloned() {
+                            self.push_notification(
+                                format!(
+                                    "{} {}",
+                                    setting.label(),
+                                    if enabled { "enabled" } else { "disabled" }
+                                ),
+                                Level::Info,
+                            );
+                            return Task::perform(read_state(setting), move |state| {
+                                Message::SettingStateUpdated(index, state)
+                            });
+                        }
                     }
                     Err(e) => {
-                        self.error_message = Some(e);
+                        log::error!("failed to apply toggle for setting {index}: {e}");
+                        self.push_notification(e.to_string(), Level::Error);
                     }
                 }
                 Task::none()
             }
-            Message::SmtStatusUpdated(status) => {
-                self.smt_status = status;
+            Message::SettingStateUpdated(index, state) => {
+                if let Some(slot) = self.states.get_mut(index) {
+                    *slot = state;
+                }
+                if index == 0 {
+                    push_tray_status(tray_status_for(state));
+                }
                 Task::none()
             }
-            Message::RefreshStatus => Task::perform(
-                async {
-                    tokio::task::spawn_blocking(|| {
-                        smt::read_smt_status().unwrap_or(SmtStatus::Unknown)
-                    })
-                    .await
-                    .unwrap_or(SmtStatus::Unknown)
-                },
-                Message::SmtStatusUpdated,
-            ),
+            Message::RefreshStatus => self.refresh_all_task(),
             Message::WindowClosed(id) => {
+                log::debug!("window {id:?} closed");
                 // Window was closed, clear the ID
                 if self.window_id == Some(id) {
                     self.window_id = None;
                 }
+                for slot in &mut self.pending_toggles {
+                    if let Some((_, _, cancel)) = slot.take() {
+                        log::info!("window closed with a pending toggle; cancelling it");
+                        cancel.cancel();
+                    }
+                }
                 Task::none()
             }
             Message::WindowOpened(id) => {
                 self.window_id = Some(id);
                 // Immediately refresh status when window becomes visible
-                Task::perform(
-                    async {
-                        tokio::task::spawn_blocking(|| {
-                            smt::read_smt_status().unwrap_or(SmtStatus::Unknown)
-                        })
-                        .await
-                        .unwrap_or(SmtStatus::Unknown)
-                    },
-                    Message::SmtStatusUpdated,
-                )
+                self.refresh_all_task()
             }
             Message::GtkTick => {
                 // Process pending GTK events for the tray icon
                 while gtk::events_pending() {
                     gtk::main_iteration_do(false);
                 }
+                notification::prune_expired(&mut self.notifications);
                 Task::none()
             }
-            Message::TrayEvent(tray_event) => match tray_event {
-                TrayEvent::ShowWindow => {
-                    if let Some(id) = self.window_id {
-                        // Window exists, just focus it
-                        window::gain_focus(id)
-                    } else {
-                        // No window, open a new one
-                        let (id, open_task) = window::open(window::Settings {
-                            size: Size::new(300.0, 200.0),
-                            resizable: false,
-                            decorations: true,
-                            ..Default::default()
-                        });
-                        self.window_id = Some(id);
-                        open_task.map(Message::WindowOpened)
+            Message::TrayEvent(tray_event) => {
+                log::debug!("dispatching tray event: {tray_event:?}");
+                match tray_event {
+                    TrayEvent::ShowWindow => {
+                        if let Some(id) = self.window_id {
+                            // Window exists, just focus it
+                            window::gain_focus(id)
+                        } else {
+                            // No window, open a new one
+                            let (id, open_task) = window::open(window::Settings {
+                                size: Size::new(300.0, 200.0),
+                                resizable: false,
+                                decorations: true,
+                                ..Default::default()
+                            });
+                            self.window_id = Some(id);
+                            open_task.map(Message::WindowOpened)
+                        }
+                    }
+                    TrayEvent::ToggleSmt => {
+                        let Some(&current) = self.states.first() else {
+                            return Task::none();
+                        };
+                        if current.is_controllable() {
+                            return self.update(Message::SettingToggled(0, !current.is_enabled()));
+                        }
+                        log::debug!(
+                            "ignoring tray toggle request; setting 0 is not controllable in state {current:?}"
+                        );
+                        Task::none()
+                    }
+                    TrayEvent::Quit => {
+                        log::info!("quit requested from tray menu");
+                        std::process::exit(0);
                     }
                 }
-                TrayEvent::Quit => {
-                    std::process::exit(0);
-                }
-            },
+            }
         }
     }
 
+    /// Refreshes every setting's state concurrently, each reported back
+    /// through its own `SettingStateUpdated(index, ...)` message.
+    fn refresh_all_task(&self) -> Task<Message> {
+        Task::batch(
+            self.settings
+                .iter()
+                .cloned()
+                .enumerate()
+                .map(|(index, setting)| {
+                    Task::perform(read_state(setting), move |state| {
+                        Message::SettingStateUpdated(index, state)
+                    })
+                }),
+        )
+    }
+
     pub fn subscription(&self) -> Subscription<Message> {
         let mut subs = vec![
             window::close_events().map(Message::WindowClosed),
@@ -169,7 +314,7 @@ impl App {
             tray_subscription(),
         ];
 
-        // Only poll SMT status when window is visible
+        // Only poll status when window is visible
         if self.window_id.is_some() {
             subs.push(iced::time::every(Duration::from_secs(3)).map(|_| Message::RefreshStatus));
         }
@@ -180,40 +325,52 @@ impl App {
     pub fn view(&self, _window_id: window::Id) -> Element<'_, Message> {
         let mut content = column![].spacing(15);
 
-        // SMT setting
-        match self.smt_status {
-            SmtStatus::On | SmtStatus::Off => {
-                let label = format!(
-                    "SMT (Hyperthreading) - {}",
-                    if self.smt_status.is_enabled() {
-                        "On"
-                    } else {
-                        "Off"
-                    }
-                );
-                let toggle = toggler(self.smt_status.is_enabled())
-                    .label(label)
-                    .on_toggle(Message::SmtToggled);
-                content = content.push(toggle);
-            }
-            SmtStatus::ForceOff => {
-                content = content.push(text("SMT (Hyperthreading) - Disabled at boot").size(14));
-            }
-            SmtStatus::NotSupported => {
-                content = content.push(text("SMT (Hyperthreading) - Not supported").size(14));
-            }
-            SmtStatus::Unknown => {
-                content = content.push(text("SMT (Hyperthreading) - Unknown").size(14));
+        for (index, (setting, state)) in self.settings.iter().zip(self.states.iter()).enumerate()
+        {
+            match state {
+                SettingState::On | SettingState::Off => {
+                    let label = format!(
+                        "{} - {}",
+                        setting.label(),
+                        if state.is_enabled() { "On" } else { "Off" }
+                    );
+                    let toggle = toggler(state.is_enabled())
+                        .label(label)
+                        .on_toggle(move |enabled| Message::SettingToggled(index, enabled));
+                    content = content.push(toggle);
+                }
+                SettingState::Locked => {
+                    content = content
+                        .push(text(format!("{} - Disabled at boot", setting.label())).size(14));
+                }
+                SettingState::NotSupported => {
+                    content = content
+                        .push(text(format!("{} - Not supported", setting.label())).size(14));
+                }
+                SettingState::Unknown => {
+                    content =
+                        content.push(text(format!("{} - Unknown", setting.label())).size(14));
+                }
             }
-        }
 
-        if self.is_toggling {
-            content = content.push(text("Applying changes...").size(12));
+            // Shown for as long as the apply is actually in flight, not on
+            // a fixed timer, so a slow pkexec auth dialog doesn't make the
+            // indicator vanish while the user is still waiting on it.
+            if self.is_toggling_index(index) {
+                content = content.push(text("Applying changes...").size(12));
+            }
         }
 
-        if let Some(ref error) = self.error_message {
-            content = content.push(text(format!("Error: {}", error)).size(12));
+        let mut notifications = column![].spacing(4);
+        for notification in &self.notifications {
+            let prefix = match notification.level {
+                Level::Info => "",
+                Level::Error => "Error: ",
+            };
+            notifications =
+                notifications.push(text(format!("{prefix}{}", notification.text)).size(12));
         }
+        content = content.push(notifications);
 
         container(content)
             .width(Length::Fill)
@@ -225,6 +382,48 @@ impl App {
     pub fn theme(&self, _window_id: window::Id) -> Theme {
         Theme::Dark
     }
+
+    /// The last known state of the setting at `index`. Exposed for tests
+    /// driving `update` directly; the UI itself only reads this through `view`.
+    pub fn setting_state(&self, index: usize) -> Option<SettingState> {
+        self.states.get(index).copied()
+    }
+
+    /// Whether any setting has a toggle request currently in flight.
+    pub fn is_toggling(&self) -> bool {
+        self.pending_toggles.iter().any(Option::is_some)
+    }
+
+    /// Whether the setting at `index` has a toggle request currently in flight.
+    fn is_toggling_index(&self, index: usize) -> bool {
+        matches!(self.pending_toggles.get(index), Some(Some(_)))
+    }
+
+    /// The currently active (not yet expired) notifications.
+    pub fn notifications(&self) -> &[Notification] {
+        &self.notifications
+    }
+
+    fn push_notification(&mut self, text: impl Into<String>, level: Level) {
+        self.notifications.push(Notification::new(text, level));
+    }
+}
+
+/// Reads a setting's current state off the blocking pool, logging (rather
+/// than surfacing) failures since this is used for passive background
+/// refreshes.
+async fn read_state(setting: Arc<dyn Setting>) -> SettingState {
+    tokio::task::spawn_blocking(move || {
+        setting.read_state().unwrap_or_else(|e| {
+            log::warn!("failed to refresh state for {}: {e}", setting.label());
+            SettingState::Unknown
+        })
+    })
+    .await
+    .unwrap_or_else(|join_err| {
+        log::error!("setting state refresh task panicked: {join_err}");
+        SettingState::Unknown
+    })
 }
 
 /// Subscription that polls the tray event channel
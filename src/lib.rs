@@ -0,0 +1,6 @@
+pub mod app;
+pub mod notification;
+pub mod privileged;
+pub mod setting;
+pub mod smt;
+pub mod tray;
@@ -1,12 +1,11 @@
-mod app;
-mod smt;
-mod tray;
-
+use std::sync::Arc;
 use std::sync::mpsc;
 
-use app::App;
 use gtk::prelude::GtkSettingsExt;
-use tray::Tray;
+use smt_toggle::app::{self, App};
+use smt_toggle::setting::Setting;
+use smt_toggle::smt::{SmtBackend, SmtSetting, SysfsBackend};
+use smt_toggle::tray::Tray;
 
 fn get_system_font() -> iced::Font {
     let Some(settings) = gtk::Settings::default() else {
@@ -31,7 +30,19 @@ fn get_system_font() -> iced::Font {
     iced::Font::with_name(leaked)
 }
 
+/// Initializes env_logger, defaulting to `info` level unless `RUST_LOG` is
+/// set or `--verbose`/`-v` was passed on the command line (which bumps the
+/// default to `debug`).
+fn init_logging() {
+    let verbose = std::env::args().any(|arg| arg == "--verbose" || arg == "-v");
+    let default_level = if verbose { "debug" } else { "info" };
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(default_level))
+        .init();
+}
+
 fn main() -> iced::Result {
+    init_logging();
+
     // Initialize GTK (required for tray icon menu)
     gtk::init().expect("Failed to initialize GTK");
 
@@ -45,10 +56,22 @@ fn main() -> iced::Result {
     app::set_tray_receiver(tray_receiver);
 
     // Initialize the tray icon with the event sender
-    let _tray = Tray::new(tray_sender).expect("Failed to create tray icon");
+    let _tray = match Tray::new(tray_sender) {
+        Ok(tray) => tray,
+        Err(err) => {
+            log::error!("failed to create tray icon: {err}");
+            std::process::exit(1);
+        }
+    };
+
+    // Let the app module push live SMT status updates back to the tray icon
+    app::set_tray_status_sender(_tray.status_sender());
+
+    let backend: Arc<dyn SmtBackend> = Arc::new(SysfsBackend);
+    let settings: Vec<Arc<dyn Setting>> = vec![Arc::new(SmtSetting::new(backend))];
 
     // Run as a daemon - this won't exit when windows close
-    iced::daemon(App::new, App::update, App::view)
+    iced::daemon(move || App::new(settings.clone()), App::update, App::view)
         .title("System Settings")
         .subscription(App::subscription)
         .theme(App::theme)
@@ -0,0 +1,47 @@
+//! Transient status messages shown at the bottom of the window: each one
+//! auto-dismisses once its `expiry` has passed, so the user gets feedback
+//! for an apply without it sticking around indefinitely.
+
+use std::time::{Duration, Instant};
+
+/// How serious a notification is, which controls how long it stays visible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Info,
+    Error,
+}
+
+impl Level {
+    fn lifetime(self) -> Duration {
+        match self {
+            Level::Info => Duration::from_secs(3),
+            Level::Error => Duration::from_secs(6),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub text: String,
+    pub level: Level,
+    expiry: Instant,
+}
+
+impl Notification {
+    pub fn new(text: impl Into<String>, level: Level) -> Self {
+        Self {
+            text: text.into(),
+            level,
+            expiry: Instant::now() + level.lifetime(),
+        }
+    }
+
+    fn is_expired(&self) -> bool {
+        Instant::now() >= self.expiry
+    }
+}
+
+/// Removes every notification in `notifications` whose expiry has passed.
+pub fn prune_expired(notifications: &mut Vec<Notification>) {
+    notifications.retain(|notification| !notification.is_expired());
+}
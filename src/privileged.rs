@@ -0,0 +1,122 @@
+//! A small runner for privileged (`pkexec`-wrapped) commands that the rest
+//! of the app can time out or cancel, so a stalled polkit auth dialog never
+//! hangs the UI indefinitely.
+
+use std::io::{self, Write};
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::smt::SmtError;
+
+/// Default time to wait for a pkexec auth dialog before giving up.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Wraps an I/O failure from spawning or talking to the `pkexec` child
+/// itself (as opposed to the sysfs control node) so it isn't misreported
+/// through the sysfs-specific `From<io::Error> for SmtError`.
+fn helper_io_err(err: io::Error) -> SmtError {
+    SmtError::HelperIo(err.to_string())
+}
+
+/// A cooperative cancellation handle for a single pending privileged
+/// command. Cloning shares the same underlying flag, so the caller can
+/// hold one half and hand the other to the blocking worker.
+#[derive(Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Runs `pkexec <program> <args>`, writing `stdin_data` to its stdin, and
+/// waits for it to finish.
+///
+/// The child is moved into its own session (via `setsid`) so it isn't tied
+/// to our controlling terminal. Completion is polled rather than awaited
+/// with a blocking `wait()` so the call can be aborted early: via `cancel`
+/// (the caller flipped the setting back, or closed the window) or via
+/// `timeout` elapsing (the auth dialog stalled).
+pub fn run_pkexec(
+    program: &str,
+    args: &[&str],
+    stdin_data: &[u8],
+    timeout: Duration,
+    cancel: &CancelToken,
+) -> Result<(), SmtError> {
+    let mut command = Command::new("pkexec");
+    command
+        .arg(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    // SAFETY: the closure only calls async-signal-safe libc functions
+    // between fork and exec, as required by `pre_exec`.
+    unsafe {
+        command.pre_exec(|| {
+            if libc::setsid() == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+
+    let mut child = command.spawn().map_err(helper_io_err)?;
+
+    if let Some(stdin) = child.stdin.as_mut() {
+        stdin.write_all(stdin_data).map_err(helper_io_err)?;
+    }
+    child.stdin.take();
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        // Check whether the child has already finished before treating a
+        // cancellation or deadline as live: if polkit auth completed in the
+        // same poll window the caller cancelled in, the write already
+        // happened and must be reported as such, not as `Cancelled`.
+        match child.try_wait().map_err(helper_io_err)? {
+            Some(status) if status.success() => {
+                log::info!("privileged command succeeded");
+                return Ok(());
+            }
+            Some(status) => {
+                log::warn!("privileged command failed: {status}");
+                return Err(SmtError::PkexecFailed {
+                    status: status.code(),
+                });
+            }
+            None => {
+                if cancel.is_cancelled() {
+                    log::info!("privileged command cancelled, killing child");
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(SmtError::Cancelled);
+                }
+
+                if Instant::now() >= deadline {
+                    log::warn!("privileged command timed out after {timeout:?}, killing child");
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(SmtError::Timeout);
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            }
+        }
+    }
+}
@@ -0,0 +1,44 @@
+//! Generalizes the single hard-coded SMT toggle into a panel of
+//! independently readable/applicable kernel settings.
+
+use crate::privileged::CancelToken;
+use crate::smt::SmtError;
+
+/// Generalized on/off state for a toggleable kernel setting, mirroring
+/// the shape of the original `SmtStatus` (on/off/locked/unsupported/
+/// unknown) without being tied to SMT specifically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingState {
+    On,
+    Off,
+    /// Can't be changed right now (e.g. forced off by a boot parameter).
+    Locked,
+    NotSupported,
+    Unknown,
+}
+
+impl SettingState {
+    pub fn is_enabled(&self) -> bool {
+        matches!(self, SettingState::On)
+    }
+
+    pub fn is_controllable(&self) -> bool {
+        matches!(self, SettingState::On | SettingState::Off)
+    }
+}
+
+/// A single toggleable kernel setting exposed in the panel. Each
+/// implementation owns its own read/apply logic (e.g. `SmtSetting` reads
+/// and writes `/sys/devices/system/cpu/smt/control`), so `App` can hold a
+/// `Vec` of these without knowing the specifics of any one setting.
+pub trait Setting: Send + Sync {
+    /// The label shown next to the toggle (or disabled text) in the UI.
+    fn label(&self) -> &str;
+
+    /// Reads the setting's current state.
+    fn read_state(&self) -> Result<SettingState, SmtError>;
+
+    /// Applies a new on/off value. `cancel` lets a pending privileged
+    /// write be aborted.
+    fn apply(&self, enabled: bool, cancel: &CancelToken) -> Result<(), SmtError>;
+}
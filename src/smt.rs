@@ -1,6 +1,10 @@
+use std::fmt;
 use std::fs;
 use std::io;
-use std::process::Command;
+use std::sync::{Arc, Mutex};
+
+use crate::privileged::{self, CancelToken};
+use crate::setting::{Setting, SettingState};
 
 const SMT_CONTROL_PATH: &str = "/sys/devices/system/cpu/smt/control";
 
@@ -35,38 +39,262 @@ impl From<&str> for SmtStatus {
     }
 }
 
-pub fn read_smt_status() -> io::Result<SmtStatus> {
-    let content = fs::read_to_string(SMT_CONTROL_PATH)?;
-    Ok(SmtStatus::from(content.as_str()))
+impl From<SmtStatus> for SettingState {
+    fn from(status: SmtStatus) -> Self {
+        match status {
+            SmtStatus::On => SettingState::On,
+            SmtStatus::Off => SettingState::Off,
+            SmtStatus::ForceOff => SettingState::Locked,
+            SmtStatus::NotSupported => SettingState::NotSupported,
+            SmtStatus::Unknown => SettingState::Unknown,
+        }
+    }
 }
 
-pub fn set_smt_enabled(enabled: bool) -> io::Result<()> {
-    let value = if enabled { "on" } else { "off" };
+/// Errors that can occur while reading or writing SMT control state.
+///
+/// These are kept distinct (rather than collapsed into a string) so callers
+/// such as the UI can match on the failure mode and show a meaningful
+/// message instead of an opaque error string. The variant is `Clone` so it
+/// can travel through `iced::Message`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SmtError {
+    /// The running kernel does not expose SMT control at all.
+    NotSupported,
+    /// Access to the control node failed with `io::ErrorKind::PermissionDenied`.
+    /// A refused pkexec authorization is reported as `PkexecFailed` instead.
+    PermissionDenied,
+    /// Any other I/O failure reading or writing the sysfs node.
+    Io(String),
+    /// The `pkexec` helper exited without success.
+    PkexecFailed { status: Option<i32> },
+    /// Spawning or talking to the `pkexec` helper process itself failed
+    /// (e.g. `pkexec` isn't installed). Kept distinct from `Io` so a
+    /// missing helper binary doesn't get reported as "SMT not supported".
+    HelperIo(String),
+    /// The pkexec auth dialog didn't complete within the allotted time.
+    Timeout,
+    /// The request was cancelled before it completed.
+    Cancelled,
+}
 
-    // Try direct write first
-    if fs::write(SMT_CONTROL_PATH, value).is_ok() {
-        return Ok(());
+impl fmt::Display for SmtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SmtError::NotSupported => write!(f, "SMT control is not supported on this system"),
+            SmtError::PermissionDenied => {
+                write!(f, "permission denied accessing SMT control")
+            }
+            SmtError::Io(msg) => write!(f, "I/O error accessing {SMT_CONTROL_PATH}: {msg}"),
+            SmtError::PkexecFailed { status } => match status {
+                Some(code) => write!(f, "pkexec exited with status {code}"),
+                None => write!(f, "pkexec was terminated by a signal"),
+            },
+            SmtError::HelperIo(msg) => write!(f, "failed to run the pkexec helper: {msg}"),
+            SmtError::Timeout => write!(f, "timed out waiting for authorization"),
+            SmtError::Cancelled => write!(f, "request was cancelled"),
+        }
     }
+}
+
+impl std::error::Error for SmtError {}
+
+impl From<io::Error> for SmtError {
+    fn from(err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::PermissionDenied => SmtError::PermissionDenied,
+            io::ErrorKind::NotFound => SmtError::NotSupported,
+            _ => SmtError::Io(err.to_string()),
+        }
+    }
+}
+
+/// Abstracts the place SMT control state is read from and written to, so
+/// the parse/apply logic above it can be exercised without real hardware
+/// or root (see `FakeBackend` and the `tests/` integration suite).
+pub trait SmtBackend: Send + Sync {
+    /// Reads the raw, unparsed contents of the control node.
+    fn read_raw(&self) -> io::Result<String>;
+
+    /// Writes a new value ("on" / "off"), performing whatever privilege
+    /// escalation is required. `cancel` lets an in-progress write be
+    /// aborted (e.g. a stalled pkexec auth prompt).
+    fn write(&self, value: &str, cancel: &CancelToken) -> Result<(), SmtError>;
+}
+
+/// The production backend: talks to the real sysfs control node, falling
+/// back to a detached, time-bounded `pkexec` helper when a direct write
+/// isn't authorized.
+pub struct SysfsBackend;
 
-    // Fall back to pkexec
-    let mut child = Command::new("pkexec")
-        .args(["tee", SMT_CONTROL_PATH])
-        .stdin(std::process::Stdio::piped())
-        .stdout(std::process::Stdio::null())
-        .spawn()?;
+impl SmtBackend for SysfsBackend {
+    fn read_raw(&self) -> io::Result<String> {
+        fs::read_to_string(SMT_CONTROL_PATH)
+    }
+
+    fn write(&self, value: &str, cancel: &CancelToken) -> Result<(), SmtError> {
+        // Try direct write first
+        match fs::write(SMT_CONTROL_PATH, value) {
+            Ok(()) => {
+                log::info!("wrote SMT control directly");
+                return Ok(());
+            }
+            Err(err) => {
+                log::debug!(
+                    "direct write to {SMT_CONTROL_PATH} failed, falling back to pkexec: {err}"
+                );
+            }
+        }
+
+        // Fall back to pkexec
+        log::info!("invoking pkexec to write SMT control");
+        privileged::run_pkexec(
+            "tee",
+            &[SMT_CONTROL_PATH],
+            value.as_bytes(),
+            privileged::DEFAULT_TIMEOUT,
+            cancel,
+        )
+    }
+}
+
+/// An in-memory backend for tests: serves scripted contents for reads and
+/// records every value it's asked to write.
+pub struct FakeBackend {
+    contents: Mutex<String>,
+    writes: Mutex<Vec<String>>,
+    reject_writes: bool,
+}
+
+impl FakeBackend {
+    /// A backend that reads back `contents` until written to, and accepts writes.
+    pub fn new(contents: impl Into<String>) -> Self {
+        Self {
+            contents: Mutex::new(contents.into()),
+            writes: Mutex::new(Vec::new()),
+            reject_writes: false,
+        }
+    }
+
+    /// A backend that always fails writes with `SmtError::PermissionDenied`,
+    /// for exercising the error path.
+    pub fn rejecting(contents: impl Into<String>) -> Self {
+        Self {
+            contents: Mutex::new(contents.into()),
+            writes: Mutex::new(Vec::new()),
+            reject_writes: true,
+        }
+    }
+
+    /// The values passed to `write`, in call order.
+    pub fn writes(&self) -> Vec<String> {
+        self.writes.lock().unwrap().clone()
+    }
+}
 
-    if let Some(stdin) = child.stdin.as_mut() {
-        use std::io::Write;
-        stdin.write_all(value.as_bytes())?;
+impl SmtBackend for FakeBackend {
+    fn read_raw(&self) -> io::Result<String> {
+        Ok(self.contents.lock().unwrap().clone())
     }
 
-    let status = child.wait()?;
-    if status.success() {
+    fn write(&self, value: &str, _cancel: &CancelToken) -> Result<(), SmtError> {
+        if self.reject_writes {
+            return Err(SmtError::PermissionDenied);
+        }
+        self.writes.lock().unwrap().push(value.to_string());
+        *self.contents.lock().unwrap() = value.to_string();
         Ok(())
-    } else {
-        Err(io::Error::new(
-            io::ErrorKind::PermissionDenied,
-            "Failed to set SMT status (pkexec failed)",
-        ))
+    }
+}
+
+pub fn read_smt_status(backend: &dyn SmtBackend) -> Result<SmtStatus, SmtError> {
+    log::debug!("reading SMT status");
+    let content = backend.read_raw().map_err(|err| {
+        log::warn!("failed to read SMT status: {err}");
+        SmtError::from(err)
+    })?;
+    let status = SmtStatus::from(content.as_str());
+    log::debug!("SMT status is {status:?}");
+    Ok(status)
+}
+
+/// Sets SMT on or off through `backend`. `cancel` lets the caller abort a
+/// pending privileged write (e.g. the user flipped the toggle back, or
+/// closed the window) without blocking indefinitely.
+pub fn set_smt_enabled(
+    enabled: bool,
+    backend: &dyn SmtBackend,
+    cancel: &CancelToken,
+) -> Result<(), SmtError> {
+    let value = if enabled { "on" } else { "off" };
+    log::info!("setting SMT control to '{value}'");
+    backend.write(value, cancel)
+}
+
+/// The SMT toggle, wired up as a `Setting` so it can sit in `App`'s panel
+/// alongside any other kernel tunables added later.
+pub struct SmtSetting {
+    backend: Arc<dyn SmtBackend>,
+}
+
+impl SmtSetting {
+    pub fn new(backend: Arc<dyn SmtBackend>) -> Self {
+        Self { backend }
+    }
+}
+
+impl Setting for SmtSetting {
+    fn label(&self) -> &str {
+        "SMT (Hyperthreading)"
+    }
+
+    fn read_state(&self) -> Result<SettingState, SmtError> {
+        read_smt_status(self.backend.as_ref()).map(SettingState::from)
+    }
+
+    fn apply(&self, enabled: bool, cancel: &CancelToken) -> Result<(), SmtError> {
+        set_smt_enabled(enabled, self.backend.as_ref(), cancel)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_known_status() {
+        assert_eq!(SmtStatus::from("on"), SmtStatus::On);
+        assert_eq!(SmtStatus::from("off"), SmtStatus::Off);
+        assert_eq!(SmtStatus::from("forceoff"), SmtStatus::ForceOff);
+        assert_eq!(SmtStatus::from("notsupported"), SmtStatus::NotSupported);
+        assert_eq!(SmtStatus::from("garbage"), SmtStatus::Unknown);
+    }
+
+    #[test]
+    fn trims_whitespace_from_the_raw_value() {
+        assert_eq!(SmtStatus::from("on\n"), SmtStatus::On);
+    }
+
+    #[test]
+    fn fake_backend_records_writes_and_updates_its_contents() {
+        let backend = FakeBackend::new("off");
+        let cancel = CancelToken::new();
+
+        assert_eq!(read_smt_status(&backend).unwrap(), SmtStatus::Off);
+
+        set_smt_enabled(true, &backend, &cancel).unwrap();
+
+        assert_eq!(backend.writes(), vec!["on".to_string()]);
+        assert_eq!(read_smt_status(&backend).unwrap(), SmtStatus::On);
+    }
+
+    #[test]
+    fn rejecting_backend_reports_permission_denied() {
+        let backend = FakeBackend::rejecting("off");
+        let cancel = CancelToken::new();
+
+        let err = set_smt_enabled(true, &backend, &cancel).unwrap_err();
+        assert_eq!(err, SmtError::PermissionDenied);
+        assert!(backend.writes().is_empty());
     }
 }
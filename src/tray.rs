@@ -1,65 +1,164 @@
 use std::sync::mpsc;
+use std::time::Duration;
 use tray_icon::{
-    Icon, TrayIcon, TrayIconBuilder,
-    menu::{Menu, MenuEvent, MenuItem},
+    Icon, MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent,
+    menu::{Menu, MenuEvent, MenuId, MenuItem},
 };
 
+use crate::smt::SmtStatus;
+
 /// Events from the tray icon that the main app should handle
 #[derive(Debug, Clone)]
 pub enum TrayEvent {
     ShowWindow,
+    ToggleSmt,
     Quit,
 }
 
+/// Handle to the running tray icon. The icon itself lives on a dedicated
+/// background thread (see `run_event_loop`); this struct only holds the
+/// channel used to push fresh `SmtStatus` values to it.
 pub struct Tray {
-    _tray_icon: TrayIcon,
+    status_sender: mpsc::Sender<SmtStatus>,
 }
 
 impl Tray {
     pub fn new(event_sender: mpsc::Sender<TrayEvent>) -> Result<Self, Box<dyn std::error::Error>> {
-        // Create menu with Show and Quit options
+        // Create menu with Show, Toggle and Quit options
         let menu = Menu::new();
         let show_item = MenuItem::new("Show", true, None);
+        let toggle_item = MenuItem::new("Toggle SMT", true, None);
         let quit_item = MenuItem::new("Quit", true, None);
 
         menu.append(&show_item)?;
+        menu.append(&toggle_item)?;
         menu.append(&quit_item)?;
 
         let show_id = show_item.id().clone();
+        let toggle_id = toggle_item.id().clone();
         let quit_id = quit_item.id().clone();
 
         // Create icon from embedded data
-        let icon = create_default_icon()?;
+        let icon = create_status_icon(SmtStatus::Unknown)?;
 
         let tray_icon = TrayIconBuilder::new()
             .with_menu(Box::new(menu))
-            .with_tooltip("System Settings")
+            .with_tooltip(tooltip_for_status(SmtStatus::Unknown))
             .with_icon(icon)
             .build()?;
 
-        // Handle menu events in a separate thread
+        log::debug!("tray icon created");
+
+        let (status_sender, status_receiver) = mpsc::channel::<SmtStatus>();
+
+        // Drive menu clicks, tray-icon clicks and status updates from a
+        // single background thread so the `TrayIcon` only has one owner.
         std::thread::spawn(move || {
-            loop {
-                if let Ok(event) = MenuEvent::receiver().recv() {
-                    if event.id == quit_id {
-                        let _ = event_sender.send(TrayEvent::Quit);
-                    } else if event.id == show_id {
-                        let _ = event_sender.send(TrayEvent::ShowWindow);
+            run_event_loop(
+                tray_icon,
+                event_sender,
+                status_receiver,
+                show_id,
+                toggle_id,
+                quit_id,
+            );
+        });
+
+        Ok(Self { status_sender })
+    }
+
+    /// Returns a cloneable sender so other parts of the app can push
+    /// status updates without holding onto the `Tray` itself.
+    pub fn status_sender(&self) -> mpsc::Sender<SmtStatus> {
+        self.status_sender.clone()
+    }
+}
+
+fn run_event_loop(
+    tray_icon: TrayIcon,
+    event_sender: mpsc::Sender<TrayEvent>,
+    status_receiver: mpsc::Receiver<SmtStatus>,
+    show_id: MenuId,
+    toggle_id: MenuId,
+    quit_id: MenuId,
+) {
+    loop {
+        if let Ok(event) = MenuEvent::receiver().try_recv() {
+            let tray_event = if event.id == quit_id {
+                Some(TrayEvent::Quit)
+            } else if event.id == show_id {
+                Some(TrayEvent::ShowWindow)
+            } else if event.id == toggle_id {
+                Some(TrayEvent::ToggleSmt)
+            } else {
+                None
+            };
+
+            if let Some(tray_event) = tray_event {
+                log::debug!("tray menu event: {tray_event:?}");
+                if event_sender.send(tray_event).is_err() {
+                    log::warn!("tray event receiver dropped, stopping tray event loop");
+                    return;
+                }
+            }
+        }
+
+        if let Ok(TrayIconEvent::Click {
+            button: MouseButton::Left,
+            button_state: MouseButtonState::Up,
+            ..
+        }) = TrayIconEvent::receiver().try_recv()
+        {
+            log::debug!("tray icon left-clicked");
+            if event_sender.send(TrayEvent::ShowWindow).is_err() {
+                log::warn!("tray event receiver dropped, stopping tray event loop");
+                return;
+            }
+        }
+
+        if let Ok(status) = status_receiver.try_recv() {
+            log::debug!("tray status updated to {status:?}");
+            tray_icon.set_tooltip(Some(tooltip_for_status(status)));
+            match create_status_icon(status) {
+                Ok(icon) => {
+                    if let Err(err) = tray_icon.set_icon(Some(icon)) {
+                        log::warn!("failed to set tray icon for status {status:?}: {err}");
                     }
                 }
+                Err(err) => log::warn!("failed to render tray icon for status {status:?}: {err}"),
             }
-        });
+        }
+
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
 
-        Ok(Self {
-            _tray_icon: tray_icon,
-        })
+fn tooltip_for_status(status: SmtStatus) -> &'static str {
+    match status {
+        SmtStatus::On => "SMT: On",
+        SmtStatus::Off => "SMT: Off",
+        SmtStatus::ForceOff => "SMT: Disabled at boot",
+        SmtStatus::NotSupported => "SMT: Not supported",
+        SmtStatus::Unknown => "SMT: Unknown",
     }
 }
 
-fn create_default_icon() -> Result<Icon, Box<dyn std::error::Error>> {
+/// Renders the CPU glyph tinted for the given status: green when SMT is on,
+/// grey when it's off, and red when it can't be controlled or is unknown.
+fn create_status_icon(status: SmtStatus) -> Result<Icon, Box<dyn std::error::Error>> {
+    let color = match status {
+        SmtStatus::On => (76, 175, 80),
+        SmtStatus::Off => (158, 158, 158),
+        SmtStatus::ForceOff | SmtStatus::NotSupported | SmtStatus::Unknown => (229, 57, 53),
+    };
+    create_cpu_icon(color)
+}
+
+fn create_cpu_icon(color: (u8, u8, u8)) -> Result<Icon, Box<dyn std::error::Error>> {
     // Create a simple 32x32 CPU-like icon
     let size = 32;
     let mut rgba = vec![0u8; size * size * 4];
+    let (r, g, b) = color;
 
     // Draw a simple CPU icon (square with pins)
     for y in 0..size {
@@ -74,11 +173,10 @@ fn create_default_icon() -> Result<Icon, Box<dyn std::error::Error>> {
                 || ((10..22).contains(&x) && !(8..24).contains(&y) && (x % 3 != 0));
 
             if in_body || on_pin {
-                // Light blue color for the icon
-                rgba[idx] = 100; // R
-                rgba[idx + 1] = 149; // G
-                rgba[idx + 2] = 237; // B
-                rgba[idx + 3] = 255; // A
+                rgba[idx] = r;
+                rgba[idx + 1] = g;
+                rgba[idx + 2] = b;
+                rgba[idx + 3] = 255;
             } else {
                 // Transparent
                 rgba[idx] = 0;
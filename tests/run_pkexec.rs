@@ -0,0 +1,145 @@
+//! Exercises `run_pkexec`'s cancellation, timeout, and success paths
+//! against a stub "pkexec" script placed on `PATH`, since invoking the
+//! real privilege-escalation helper isn't available (or safe) in CI.
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use smt_toggle::privileged::{CancelToken, run_pkexec};
+use smt_toggle::smt::SmtError;
+
+/// `run_pkexec` always invokes the binary literally named "pkexec" found on
+/// `PATH`. `PATH` is process-wide, so serialize the tests in this file that
+/// install a stub there.
+static PATH_LOCK: Mutex<()> = Mutex::new(());
+
+/// Installs an executable shell script named "pkexec" into a temp directory
+/// keyed by the process id and the script's length (reused if it already
+/// exists) and prepends that directory to `PATH` for the duration of the
+/// guard, restoring the original `PATH` on drop.
+struct StubPkexec {
+    original_path: Option<String>,
+    pid_file: std::path::PathBuf,
+}
+
+impl StubPkexec {
+    fn install(script: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!(
+            "smt-toggle-test-pkexec-{}-{}",
+            std::process::id(),
+            script.len()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let bin_path = dir.join("pkexec");
+        fs::write(&bin_path, script).unwrap();
+        fs::set_permissions(&bin_path, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let pid_file = dir.join("pid");
+
+        let original_path = std::env::var("PATH").ok();
+        let new_path = format!(
+            "{}:{}",
+            dir.display(),
+            original_path.clone().unwrap_or_default()
+        );
+        // SAFETY: serialized by `PATH_LOCK`, and no other thread in this
+        // process spawns subprocesses that depend on `PATH` concurrently.
+        unsafe {
+            std::env::set_var("PATH", new_path);
+            std::env::set_var("PKEXEC_PID_FILE", &pid_file);
+        }
+
+        Self {
+            original_path,
+            pid_file,
+        }
+    }
+
+    /// The pid the stub script recorded for itself, once it has started.
+    fn recorded_pid(&self) -> Option<u32> {
+        fs::read_to_string(&self.pid_file)
+            .ok()
+            .and_then(|s| s.trim().parse().ok())
+    }
+}
+
+impl Drop for StubPkexec {
+    fn drop(&mut self) {
+        // SAFETY: see `install`.
+        unsafe {
+            match &self.original_path {
+                Some(path) => std::env::set_var("PATH", path),
+                None => std::env::remove_var("PATH"),
+            }
+            std::env::remove_var("PKEXEC_PID_FILE");
+        }
+    }
+}
+
+/// A script that records its own pid, drains stdin, and then sleeps far
+/// longer than any test's timeout/cancel window - i.e. a stalled auth dialog.
+const STALLED_SCRIPT: &str = "#!/bin/sh\necho $$ > \"$PKEXEC_PID_FILE\"\ncat >/dev/null\nsleep 30\n";
+
+fn assert_reaped(pid: u32) {
+    assert!(
+        !Path::new(&format!("/proc/{pid}")).exists(),
+        "child process {pid} was not reaped after run_pkexec returned"
+    );
+}
+
+#[test]
+fn cancelling_a_stalled_command_kills_it_and_reports_cancelled() {
+    let _lock = PATH_LOCK.lock().unwrap();
+    let stub = StubPkexec::install(STALLED_SCRIPT);
+
+    let cancel = CancelToken::new();
+    let cancel_for_timer = cancel.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(150));
+        cancel_for_timer.cancel();
+    });
+
+    let result = run_pkexec("true", &[], b"", Duration::from_secs(10), &cancel);
+    assert_eq!(result, Err(SmtError::Cancelled));
+
+    if let Some(pid) = stub.recorded_pid() {
+        assert_reaped(pid);
+    }
+}
+
+#[test]
+fn a_stalled_command_times_out() {
+    let _lock = PATH_LOCK.lock().unwrap();
+    let stub = StubPkexec::install(STALLED_SCRIPT);
+
+    let cancel = CancelToken::new();
+    let result = run_pkexec("true", &[], b"", Duration::from_millis(200), &cancel);
+    assert_eq!(result, Err(SmtError::Timeout));
+
+    if let Some(pid) = stub.recorded_pid() {
+        assert_reaped(pid);
+    }
+}
+
+#[test]
+fn a_quick_successful_command_reports_ok() {
+    let _lock = PATH_LOCK.lock().unwrap();
+    let _stub = StubPkexec::install("#!/bin/sh\ncat >/dev/null\nexit 0\n");
+
+    let cancel = CancelToken::new();
+    let result = run_pkexec("true", &[], b"hello", Duration::from_secs(10), &cancel);
+    assert_eq!(result, Ok(()));
+}
+
+#[test]
+fn a_failing_command_reports_pkexec_failed() {
+    let _lock = PATH_LOCK.lock().unwrap();
+    let _stub = StubPkexec::install("#!/bin/sh\ncat >/dev/null\nexit 1\n");
+
+    let cancel = CancelToken::new();
+    let result = run_pkexec("true", &[], b"", Duration::from_secs(10), &cancel);
+    assert_eq!(result, Err(SmtError::PkexecFailed { status: Some(1) }));
+}